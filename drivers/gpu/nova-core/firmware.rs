@@ -6,9 +6,7 @@ use kernel::firmware;
 pub(crate) struct ModInfoBuilder<const N: usize>(firmware::ModInfoBuilder<N>);
 
 impl<const N: usize> ModInfoBuilder<N> {
-    const fn make_entry_file(self, chipset: &[u8], fw: &[u8]) -> Self {
-        let version = b"535.113.01";
-
+    const fn make_entry_file(self, chipset: &[u8], fw: &[u8], version: &[u8]) -> Self {
         ModInfoBuilder(
             self.0
                 .prepare()
@@ -22,11 +20,24 @@ impl<const N: usize> ModInfoBuilder<N> {
         )
     }
 
+    // Declare a `.modinfo` entry for every candidate GSP version, so all of them are packaged
+    // even though only the first one that loads is actually used at runtime.
+    const fn make_entry_versions(mut self, chipset: &[u8], fw: &[u8]) -> Self {
+        let mut i = 0;
+
+        while i < gpu::GSP_FW_VERSIONS.len() {
+            self = self.make_entry_file(chipset, fw, gpu::GSP_FW_VERSIONS[i].as_bytes());
+            i += 1;
+        }
+
+        self
+    }
+
     const fn make_entry_chipset(self, chipset: &[u8]) -> Self {
-        self.make_entry_file(chipset, b"booter_load")
-            .make_entry_file(chipset, b"booter_unload")
-            .make_entry_file(chipset, b"bootloader")
-            .make_entry_file(chipset, b"gsp")
+        self.make_entry_versions(chipset, b"booter_load")
+            .make_entry_versions(chipset, b"booter_unload")
+            .make_entry_versions(chipset, b"bootloader")
+            .make_entry_versions(chipset, b"gsp")
     }
 
     pub(crate) const fn create(
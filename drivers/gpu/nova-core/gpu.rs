@@ -3,7 +3,8 @@
 use kernel::device::Device;
 use kernel::types::ARef;
 use kernel::{
-    device, devres::Devres, error::code::*, firmware, fmt, pci, prelude::*, str::BStr, str::CString,
+    c_str, device, devres::Devres, error::code::*, firmware, fmt, pci, prelude::*, str::BStr,
+    str::CStr, str::CString,
 };
 
 use crate::driver::Bar0;
@@ -183,14 +184,42 @@ pub(crate) struct Firmware {
     gsp: firmware::Firmware,
 }
 
+/// Maximum number of GSP firmware version candidates a chipset may fall back across.
+///
+/// This is a small, fixed bound so the candidate paths can be built on the stack; bump it if a
+/// chipset ever needs to list more fallback versions than it allows.
+const MAX_FW_VERSIONS: usize = 4;
+
 impl Firmware {
-    fn new(dev: &device::Device, spec: &Spec, ver: &str) -> Result<Firmware> {
+    /// Load the firmware blobs for `spec`, trying each of `versions` in order and falling back to
+    /// the next one whenever a given version is missing (mirroring an A/B slot fallback scheme).
+    fn new(dev: &device::Device, spec: &Spec, versions: &[&str]) -> Result<Firmware> {
         let mut chip_name = CString::try_from_fmt(fmt!("{}", spec.chipset))?;
         chip_name.make_ascii_lowercase();
 
         let request = |name_| {
-            CString::try_from_fmt(fmt!("nvidia/{}/gsp/{}-{}.bin", &*chip_name, name_, ver))
-                .and_then(|path| firmware::Firmware::request(&path, dev))
+            debug_assert!(
+                versions.len() <= MAX_FW_VERSIONS,
+                "GSP_FW_VERSIONS has more candidates than MAX_FW_VERSIONS; later ones are dropped"
+            );
+            let n = versions.len().min(MAX_FW_VERSIONS);
+            let mut paths: [Option<CString>; MAX_FW_VERSIONS] = Default::default();
+
+            for (path, ver) in paths.iter_mut().zip(&versions[..n]) {
+                *path = Some(CString::try_from_fmt(fmt!(
+                    "nvidia/{}/gsp/{}-{}.bin",
+                    &*chip_name,
+                    name_,
+                    ver
+                ))?);
+            }
+
+            let mut refs: [&CStr; MAX_FW_VERSIONS] = [c_str!(""); MAX_FW_VERSIONS];
+            for (r, path) in refs.iter_mut().zip(paths[..n].iter()) {
+                *r = path.as_deref().unwrap();
+            }
+
+            firmware::Firmware::request_first_of(&refs[..n], dev)
         };
 
         Ok(Firmware {
@@ -213,10 +242,17 @@ pub(crate) struct Gpu {
     timer: Timer,
 }
 
+/// GSP firmware versions to try, in order of preference.
+///
+/// The first entry is the version Nova Core is primarily developed and tested against; the
+/// remaining ones are older known-good releases to fall back to if it isn't installed. Also used
+/// by [`crate::firmware::ModInfoBuilder`] to declare `.modinfo` entries for every candidate.
+pub(crate) const GSP_FW_VERSIONS: &[&str] = &["535.113.01", "525.60.13", "515.76"];
+
 impl Gpu {
     pub(crate) fn new(pdev: &pci::Device, bar: Devres<Bar0>) -> Result<impl PinInit<Self>> {
         let spec = Spec::new(&bar)?;
-        let fw = Firmware::new(pdev.as_ref(), &spec, "535.113.01")?;
+        let fw = Firmware::new(pdev.as_ref(), &spec, GSP_FW_VERSIONS)?;
 
         dev_info!(
             pdev.as_ref(),
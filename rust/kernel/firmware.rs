@@ -4,7 +4,18 @@
 //!
 //! C header: [`include/linux/firmware.h`](srctree/include/linux/firmware.h)
 
-use crate::{bindings, device::Device, error::Error, error::Result, str::CStr};
+use crate::{
+    alloc::{flags::GFP_KERNEL, KBox, KVec},
+    bindings, c_str,
+    device::Device,
+    error::code::{EINVAL, EKEYREJECTED, ENOENT, ENOMEM},
+    error::Error,
+    error::Result,
+    str::CStr,
+    ThisModule,
+};
+use core::marker::PhantomData;
+use core::pin::Pin;
 use core::ptr::NonNull;
 
 /// # Invariants
@@ -54,6 +65,56 @@ impl FwFunc {
 /// ```
 pub struct Firmware(NonNull<bindings::firmware>);
 
+/// Description of a public key to verify a detached firmware signature against, as accepted by
+/// [`Firmware::request_verified`].
+///
+/// The key must already be enrolled in one of the kernel's asymmetric-key keyrings (builtin,
+/// secondary, or platform), the same way module-signing keys are, since verification goes through
+/// `request_key()` and the asymmetric-key subsystem's `verify_signature()`. There is no standalone
+/// "verify against these raw key bytes" primitive in the kernel's crypto API to fall back to.
+pub struct FirmwareKey<'a>(pub &'a CStr);
+
+/// A firmware request that loads directly into a caller-provided buffer, returned by
+/// [`Firmware::request_into_buf`].
+///
+/// Unlike [`Firmware`], the loaded data is not owned by this type: it lives in the buffer the
+/// caller supplied (e.g. a DMA-coherent allocation), which must outlive this handle. Dropping it
+/// still calls `bindings::release_firmware`, which only releases the kernel's internal
+/// `struct firmware` bookkeeping, not the externally-owned buffer.
+///
+/// # Invariants
+///
+/// The pointer is valid, and has ownership over the instance of `struct firmware`. Its `data`
+/// field aliases the buffer supplied to [`Firmware::request_into_buf`], which outlives `'a`.
+pub struct FirmwareIntoBuf<'a>(NonNull<bindings::firmware>, PhantomData<&'a mut [u8]>);
+
+impl FirmwareIntoBuf<'_> {
+    fn as_raw(&self) -> *mut bindings::firmware {
+        self.0.as_ptr()
+    }
+
+    /// Returns the number of bytes the firmware loader wrote into the caller's buffer.
+    pub fn size(&self) -> usize {
+        // SAFETY: `self.as_raw()` is valid by the type invariant.
+        unsafe { (*self.as_raw()).size }
+    }
+
+    /// Returns the written portion of the caller's buffer.
+    pub fn data(&self) -> &[u8] {
+        // SAFETY: `self.as_raw()` is valid by the type invariant. Additionally,
+        // `bindings::firmware` guarantees, if successfully requested, that
+        // `bindings::firmware::data` has a size of `bindings::firmware::size` bytes.
+        unsafe { core::slice::from_raw_parts((*self.as_raw()).data, self.size()) }
+    }
+}
+
+impl Drop for FirmwareIntoBuf<'_> {
+    fn drop(&mut self) {
+        // SAFETY: `self.as_raw()` is valid by the type invariant.
+        unsafe { bindings::release_firmware(self.as_raw()) };
+    }
+}
+
 impl Firmware {
     fn request_internal(name: &CStr, dev: &Device, func: FwFunc) -> Result<Self> {
         let mut fw: *mut bindings::firmware = core::ptr::null_mut();
@@ -82,6 +143,110 @@ impl Firmware {
         Self::request_internal(name, dev, FwFunc::request_nowarn())
     }
 
+    /// Try each of `paths` in order, returning the first one that loads successfully.
+    ///
+    /// This mirrors the A/B slot fallback pattern used by bootloaders: a driver can list its
+    /// preferred firmware revision first, followed by older known-good ones, and transparently
+    /// fall back to the next candidate whenever the previous one is missing (`ENOENT`). Any other
+    /// error (`EACCES`, `ENOMEM`, ...) is returned immediately instead of being masked by trying
+    /// further candidates. Candidates are loaded with [`Firmware::request`]; their contents are
+    /// not verified. If every candidate up to
+    /// and including the last is missing, the `ENOENT` of the *last* one is returned.
+    ///
+    /// `paths` must not be empty.
+    pub fn request_first_of(paths: &[&CStr], dev: &Device) -> Result<Self> {
+        let (last, rest) = paths.split_last().ok_or(EINVAL)?;
+
+        for path in rest {
+            match Self::request(path, dev) {
+                Ok(fw) => return Ok(fw),
+                Err(e) if e.to_errno() == ENOENT.to_errno() => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Self::request(last, dev)
+    }
+
+    /// Request `name`, together with a detached signature at `sig_name`, and verify the firmware
+    /// against it with `key` before handing it back.
+    ///
+    /// The firmware image is hashed in full and the digest is checked against the signature
+    /// using the kernel's crypto API. The firmware is only returned once verification has
+    /// succeeded; on a mismatch, `Err(EKEYREJECTED)` is returned and the firmware data is never
+    /// exposed to the caller. The signature's own backing buffer is released (its [`Firmware`]
+    /// handle is dropped) before this function returns, whether verification succeeds or not.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use kernel::{c_str, device::Device, firmware::{Firmware, FirmwareKey}};
+    ///
+    /// # fn no_run() -> Result<(), Error> {
+    /// # // SAFETY: *NOT* safe, just for the example to get an `ARef<Device>` instance
+    /// # let dev = unsafe { Device::get_device(core::ptr::null_mut()) };
+    ///
+    /// let fw = Firmware::request_verified(
+    ///     c_str!("path/to/firmware.bin"),
+    ///     c_str!("path/to/firmware.bin.sig"),
+    ///     FirmwareKey(c_str!("nvidia-gsp")),
+    ///     &dev,
+    /// )?;
+    /// let blob = fw.data();
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn request_verified(
+        name: &CStr,
+        sig_name: &CStr,
+        key: FirmwareKey<'_>,
+        dev: &Device,
+    ) -> Result<Self> {
+        let fw = Self::request(name, dev)?;
+        let sig = Self::request(sig_name, dev)?;
+
+        verify_detached_signature(fw.data(), sig.data(), key)?;
+
+        Ok(fw)
+    }
+
+    /// Request `name` and have the firmware loader write it directly into `buf`, instead of
+    /// allocating its own buffer. See also `bindings::request_firmware_into_buf`.
+    ///
+    /// This avoids an extra copy for large images headed straight for device-visible memory
+    /// (e.g. a DMA-coherent allocation for a GSP image). `buf` must be large enough to hold the
+    /// firmware, and must outlive the returned [`FirmwareIntoBuf`]; its
+    /// [`FirmwareIntoBuf::size`] reports how many bytes were actually written.
+    pub fn request_into_buf<'a>(
+        name: &CStr,
+        dev: &Device,
+        buf: &'a mut [u8],
+    ) -> Result<FirmwareIntoBuf<'a>> {
+        let mut fw: *mut bindings::firmware = core::ptr::null_mut();
+        let pfw: *mut *mut bindings::firmware = &mut fw;
+
+        // SAFETY: `pfw` is a valid pointer to a NULL initialized `bindings::firmware` pointer.
+        // `name` and `dev` are valid as by their type invariants. `buf` is valid for `buf.len()`
+        // bytes for the duration of the call, and outlives the returned `FirmwareIntoBuf<'a>`.
+        let ret = unsafe {
+            bindings::request_firmware_into_buf(
+                pfw as _,
+                name.as_char_ptr(),
+                dev.as_raw(),
+                buf.as_mut_ptr() as *mut core::ffi::c_void,
+                buf.len(),
+            )
+        };
+        if ret != 0 {
+            return Err(Error::from_errno(ret));
+        }
+
+        // SAFETY: `ret == 0` guarantees that `fw` is a valid pointer to `bindings::firmware`,
+        // whose `data` the C API guarantees to alias `buf` for up to `buf.len()` bytes.
+        Ok(FirmwareIntoBuf(unsafe { NonNull::new_unchecked(fw) }, PhantomData))
+    }
+
     fn as_raw(&self) -> *mut bindings::firmware {
         self.0.as_ptr()
     }
@@ -116,6 +281,135 @@ unsafe impl Send for Firmware {}
 // be used from any thread.
 unsafe impl Sync for Firmware {}
 
+// SAFETY: `FirmwareIntoBuf` only holds a pointer to a C `struct firmware` and a marker for the
+// borrow of the caller's buffer, both of which are safe to be used from any thread.
+unsafe impl Send for FirmwareIntoBuf<'_> {}
+
+// SAFETY: `FirmwareIntoBuf` only holds a pointer to a C `struct firmware` and a marker for the
+// borrow of the caller's buffer, references to which are safe to be used from any thread.
+unsafe impl Sync for FirmwareIntoBuf<'_> {}
+
+/// Hashes `data` (SHA-256) and verifies `signature` over the resulting digest against `key`,
+/// using the kernel's asymmetric-key subsystem.
+fn verify_detached_signature(data: &[u8], signature: &[u8], key: FirmwareKey<'_>) -> Result<()> {
+    let digest = Sha256Digest::compute(data)?;
+
+    // SAFETY: `bindings::key_type_asymmetric` is a statically-allocated `struct key_type`;
+    // `key.0` is a valid, NUL-terminated key description.
+    let key_ref = unsafe {
+        bindings::request_key(
+            &bindings::key_type_asymmetric,
+            key.0.as_char_ptr(),
+            core::ptr::null(),
+        )
+    };
+
+    // SAFETY: `request_key()` returns either a valid `struct key *` or an `ERR_PTR`-encoded
+    // error.
+    if unsafe { bindings::IS_ERR(key_ref as *const _) } {
+        // SAFETY: checked above that `key_ref` encodes an error.
+        return Err(Error::from_errno(unsafe {
+            bindings::PTR_ERR(key_ref as *const _) as i32
+        }));
+    }
+
+    let mut sig = bindings::public_key_signature::default();
+    sig.s = signature.as_ptr() as *mut u8;
+    sig.s_size = signature.len() as u32;
+    sig.digest = digest.as_bytes().as_ptr() as *mut u8;
+    sig.digest_size = digest.as_bytes().len() as u32;
+    sig.hash_algo = c_str!("sha256").as_char_ptr();
+    sig.pkey_algo = c_str!("rsa").as_char_ptr();
+    sig.encoding = c_str!("pkcs1").as_char_ptr();
+
+    // SAFETY: `key_ref` was just checked to be a valid, non-error `struct key *`; `sig`'s `s` and
+    // `digest` pointers are valid for `s_size`/`digest_size` bytes for the duration of the call.
+    // `key_put()` is called unconditionally below, regardless of the verification's outcome.
+    let ret = unsafe { bindings::verify_signature(key_ref, &sig) };
+
+    // SAFETY: `key_ref` is a valid, referenced `struct key *` obtained from `request_key()`
+    // above, and is released exactly once, here.
+    unsafe { bindings::key_put(key_ref) };
+
+    if ret != 0 {
+        return Err(EKEYREJECTED);
+    }
+
+    Ok(())
+}
+
+/// A SHA-256 digest, computed via the kernel's `crypto_shash` API.
+struct Sha256Digest([u8; Self::SIZE]);
+
+impl Sha256Digest {
+    const SIZE: usize = 32;
+
+    fn compute(data: &[u8]) -> Result<Self> {
+        // SAFETY: `c_str!("sha256")` is a valid, NUL-terminated algorithm name; `0, 0` request an
+        // unkeyed hash with no type/mask restriction, matching `crypto_alloc_shash`'s contract.
+        let tfm = unsafe { bindings::crypto_alloc_shash(c_str!("sha256").as_char_ptr(), 0, 0) };
+
+        // SAFETY: `crypto_alloc_shash` returns either a valid `struct crypto_shash *` or an
+        // `ERR_PTR`-encoded error.
+        if unsafe { bindings::IS_ERR(tfm as *const _) } {
+            // SAFETY: checked above that `tfm` encodes an error.
+            return Err(Error::from_errno(unsafe { bindings::PTR_ERR(tfm as *const _) as i32 }));
+        }
+
+        // SAFETY: `tfm` was just checked to be a valid, non-error pointer.
+        if unsafe { bindings::crypto_shash_digestsize(tfm) } as usize != Self::SIZE {
+            // SAFETY: `tfm` is a valid pointer, released exactly once, here.
+            unsafe { bindings::crypto_free_shash(tfm) };
+            return Err(EINVAL);
+        }
+
+        // SAFETY: `tfm` was just checked to be a valid, non-error pointer.
+        let desc_size = unsafe { bindings::crypto_shash_descsize(tfm) } as usize;
+
+        // The C `struct shash_desc` is a `tfm` pointer followed by `desc_size` bytes of
+        // transform-private scratch space; allocate it as a byte buffer and overlay the fixed
+        // `tfm` field at its start.
+        let mut desc_storage: KVec<u8> = KVec::new();
+        let resized = desc_storage.resize(
+            core::mem::size_of::<bindings::shash_desc>() + desc_size,
+            0,
+            GFP_KERNEL,
+        );
+
+        if resized.is_err() {
+            // SAFETY: `tfm` is a valid pointer, released exactly once, here.
+            unsafe { bindings::crypto_free_shash(tfm) };
+            return Err(ENOMEM);
+        }
+
+        let desc = desc_storage.as_mut_ptr() as *mut bindings::shash_desc;
+        // SAFETY: `desc` is valid for a `bindings::shash_desc` write, as sized above.
+        unsafe { (*desc).tfm = tfm };
+
+        let mut out = [0u8; Self::SIZE];
+
+        // SAFETY: `desc` is a valid, initialized `shash_desc` backed by `desc_size` bytes of
+        // scratch space; `data` is valid for `data.len()` bytes; `out` is a buffer of
+        // `Self::SIZE` bytes, matching the digest size checked above.
+        let ret = unsafe {
+            bindings::crypto_shash_digest(desc, data.as_ptr(), data.len() as u32, out.as_mut_ptr())
+        };
+
+        // SAFETY: `tfm` is a valid pointer, released exactly once, here.
+        unsafe { bindings::crypto_free_shash(tfm) };
+
+        if ret != 0 {
+            return Err(Error::from_errno(ret));
+        }
+
+        Ok(Self(out))
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
 /// Create firmware .modinfo entries.
 ///
 /// This macro is the counterpart of the C macro `MODULE_FIRMWARE()`, but instead of taking a
@@ -292,3 +586,190 @@ impl ModInfoBuilder<0> {
         self.n + 1
     }
 }
+
+/// Progress of an ongoing firmware upload, reported back to userspace through sysfs.
+///
+/// There is no callback to report a completion percentage directly: the upload framework derives
+/// the "progress" sysfs attribute itself, from the offsets and sizes the driver has already
+/// reported through [`FirmwareUploadOps::write`].
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum FirmwareUploadProgress {
+    /// The device is still applying the image.
+    Busy,
+    /// The upload completed successfully.
+    Done,
+    /// The device rejected the image or otherwise failed to apply it.
+    Error,
+}
+
+/// Callbacks implemented by a driver to expose a sysfs-driven firmware update (flashing) path,
+/// as driven by `bindings::firmware_upload_register`.
+///
+/// This mirrors the erase-once/write-many discipline of a typical firmware updater: the subsystem
+/// hands the driver the incoming image in bounded chunks with explicit offsets, lets the driver
+/// report progress through [`FirmwareUploadOps::poll_complete`], and surfaces cancellation
+/// through [`FirmwareUploadOps::cancel`].
+pub trait FirmwareUploadOps: Sync {
+    /// Prepare the device to receive a new firmware image of `size` bytes.
+    fn prepare(&self, size: usize) -> Result;
+
+    /// Write `data` to the device, `data.len()` bytes starting at `offset` within the overall
+    /// image.
+    ///
+    /// Returns the number of bytes actually written, which may be less than `data.len()` if the
+    /// device can only accept a smaller chunk at a time; the subsystem resumes the next call at
+    /// `offset` plus the returned count.
+    fn write(&self, data: &[u8], offset: usize) -> Result<usize>;
+
+    /// Poll the status of an ongoing upload.
+    fn poll_complete(&self) -> Result<FirmwareUploadProgress>;
+
+    /// Cancel an ongoing upload.
+    fn cancel(&self);
+}
+
+/// Trampolines from the C `bindings::fw_upload_ops` vtable to a [`FirmwareUploadOps`]
+/// implementation, reached back through `fw_upload->dd_handle`.
+struct Adapter<T>(PhantomData<T>);
+
+impl<T: FirmwareUploadOps> Adapter<T> {
+    // SAFETY: `fw_upload` is valid for the duration of the call, as guaranteed by the C caller,
+    // and its `dd_handle` is the `T` that was passed to `firmware_upload_register` as
+    // `dev_fw_update_priv`, which outlives the registration by the type invariants of
+    // [`FirmwareUploadRegistration`].
+    unsafe fn from_context<'a>(fw_upload: *mut bindings::fw_upload) -> &'a T {
+        // SAFETY: guaranteed by the caller.
+        unsafe { &*((*fw_upload).dd_handle as *const T) }
+    }
+
+    // SAFETY: `fw_upload` is valid for the duration of the call, as guaranteed by the C caller.
+    unsafe extern "C" fn prepare(
+        fw_upload: *mut bindings::fw_upload,
+        data: *const u8,
+        size: u32,
+    ) -> i32 {
+        // SAFETY: by the function's safety requirements.
+        let this = unsafe { Self::from_context(fw_upload) };
+        let _ = data;
+
+        match this.prepare(size as usize) {
+            Ok(()) => 0,
+            Err(e) => e.to_errno(),
+        }
+    }
+
+    // SAFETY: `fw_upload` is valid for the duration of the call, as guaranteed by the C caller;
+    // `data` is valid for `size` bytes.
+    unsafe extern "C" fn write(
+        fw_upload: *mut bindings::fw_upload,
+        data: *const u8,
+        offset: u32,
+        size: u32,
+        written: *mut u32,
+    ) -> i32 {
+        // SAFETY: by the function's safety requirements.
+        let this = unsafe { Self::from_context(fw_upload) };
+        // SAFETY: `data` is valid for `size` bytes, as guaranteed by the C caller.
+        let slice = unsafe { core::slice::from_raw_parts(data, size as usize) };
+
+        match this.write(slice, offset as usize) {
+            Ok(n) => {
+                // SAFETY: `written` is a valid pointer to a `u32`, as guaranteed by the C caller.
+                unsafe { *written = n as u32 };
+                0
+            }
+            Err(e) => e.to_errno(),
+        }
+    }
+
+    // SAFETY: `fw_upload` is valid for the duration of the call, as guaranteed by the C caller.
+    unsafe extern "C" fn poll_complete(fw_upload: *mut bindings::fw_upload) -> i32 {
+        // SAFETY: by the function's safety requirements.
+        let this = unsafe { Self::from_context(fw_upload) };
+
+        match this.poll_complete() {
+            Ok(FirmwareUploadProgress::Busy) => bindings::FW_UPLOAD_PROG_BUSY as i32,
+            Ok(FirmwareUploadProgress::Done) => bindings::FW_UPLOAD_PROG_IDLE as i32,
+            Ok(FirmwareUploadProgress::Error) => bindings::FW_UPLOAD_PROG_ERROR as i32,
+            Err(e) => e.to_errno(),
+        }
+    }
+
+    // SAFETY: `fw_upload` is valid for the duration of the call, as guaranteed by the C caller.
+    unsafe extern "C" fn cancel(fw_upload: *mut bindings::fw_upload) {
+        // SAFETY: by the function's safety requirements.
+        let this = unsafe { Self::from_context(fw_upload) };
+
+        this.cancel();
+    }
+
+    // `cleanup` is invoked after an upload is done with (successfully or not) so the driver can
+    // release any resources held across `prepare`/`write`/`poll_complete`. [`FirmwareUploadOps`]
+    // has no such step to call back into, so this is left unset.
+    const VTABLE: bindings::fw_upload_ops = bindings::fw_upload_ops {
+        prepare: Some(Self::prepare),
+        write: Some(Self::write),
+        poll_complete: Some(Self::poll_complete),
+        cancel: Some(Self::cancel),
+        cleanup: None,
+    };
+}
+
+/// A registered firmware-upload endpoint.
+///
+/// Exposes a sysfs-driven device-firmware update path named `name`, backed by
+/// `bindings::firmware_upload_register`, and calling back into `ops` for the lifecycle of each
+/// upload. The endpoint is unregistered automatically when this handle is dropped.
+///
+/// # Invariants
+///
+/// The pointer is valid and has ownership over the `bindings::fw_upload` instance returned by
+/// `firmware_upload_register`.
+pub struct FirmwareUploadRegistration<T: FirmwareUploadOps> {
+    fw_upload: NonNull<bindings::fw_upload>,
+    ops: Pin<KBox<T>>,
+}
+
+impl<T: FirmwareUploadOps> FirmwareUploadRegistration<T> {
+    /// Register a new firmware-upload endpoint named `name` for `dev`, dispatching to `ops`.
+    ///
+    /// `module` is pinned by the firmware-upload core for as long as a flash may be in progress,
+    /// the same way the owning module is threaded through this crate's other registration APIs.
+    pub fn new(dev: &Device, name: &CStr, ops: Pin<KBox<T>>, module: &ThisModule) -> Result<Self> {
+        let context = &*ops as *const T as *mut core::ffi::c_void;
+
+        // SAFETY: `module` is valid as per its type invariants; `dev` is valid as per its type
+        // invariants; `name` is NUL-terminated; `context` is valid for as long as `ops` is kept
+        // alive, which outlives the registration below because it is stored alongside the
+        // returned handle.
+        let fw_upload = unsafe {
+            bindings::firmware_upload_register(
+                module.as_ptr(),
+                dev.as_raw(),
+                name.as_char_ptr(),
+                &Adapter::<T>::VTABLE as *const _ as *mut _,
+                context,
+            )
+        };
+
+        let fw_upload = NonNull::new(fw_upload).ok_or(ENOMEM)?;
+
+        Ok(Self { fw_upload, ops })
+    }
+}
+
+impl<T: FirmwareUploadOps> Drop for FirmwareUploadRegistration<T> {
+    fn drop(&mut self) {
+        // SAFETY: `self.fw_upload` is valid by the type invariant, and is unregistered exactly
+        // once, here.
+        unsafe { bindings::firmware_upload_unregister(self.fw_upload.as_ptr()) };
+    }
+}
+
+// SAFETY: `FirmwareUploadRegistration` only holds a pointer to a C `struct fw_upload` and a boxed
+// `T: FirmwareUploadOps`, both of which are safe to be used from any thread.
+unsafe impl<T: FirmwareUploadOps + Send> Send for FirmwareUploadRegistration<T> {}
+
+// SAFETY: `FirmwareUploadRegistration` only holds a pointer to a C `struct fw_upload` and a boxed
+// `T: FirmwareUploadOps`, references to which are safe to be used from any thread.
+unsafe impl<T: FirmwareUploadOps + Sync> Sync for FirmwareUploadRegistration<T> {}
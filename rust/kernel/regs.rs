@@ -0,0 +1,192 @@
+// SPDX-License-Identifier: GPL-2.0
+
+//! Generic MMIO register abstractions.
+//!
+//! This module provides the [`register!`] macro, which turns a register's offset and bitfield
+//! layout into a typed newtype with per-field accessors, so that drivers no longer need to
+//! hand-write `*_SHIFT`/`*_MASK` constants and the shifting/masking logic that goes with them.
+
+/// Declares a typed MMIO register and its bitfields.
+///
+/// Each invocation generates a `Copy`, `Clone`, `PartialEq`, `Eq` newtype wrapping the register's
+/// raw `u32` value, together with:
+///
+/// - `read(bar: &$io) -> Self`, backed by `$io::readl` at the given offset.
+/// - `write(bar: &$io, value: u32)`, backed by `$io::writel`, unless the register is declared
+///   `ro` (read-only), in which case no `write` is generated.
+/// - A getter for every field, named after it, which masks the raw value and then shifts it down
+///   to the field's base (mask-then-shift).
+/// - A setter for every field that is given a second, `/`-separated name. The setter shifts the
+///   new value up and masks it to the field's width (shift-then-mask), clears the field's bits in
+///   the raw value, and ORs the result in, leaving every other field untouched.
+///
+/// Fields are given as inclusive `high:low` bit ranges. A field must not overlap any other field,
+/// unless it is declared as a composite of other fields with `= composite(a, b, ...)` instead of a
+/// bit range of its own (e.g. a "chipset" field that is the union of an "implementation" and an
+/// "architecture" field): its mask is derived by ORing together the masks of the named fields, and
+/// its shift is the lowest of theirs. A composite field only gets a getter, since there is no
+/// single well-defined way to write to the union of two independently addressable fields, and it
+/// may only reference plain (non-composite) fields of the same register.
+///
+/// The `$io` type and offset are each wrapped in parentheses, to disambiguate them from the `@`
+/// separating them (`macro_rules!` otherwise rejects a `ty`/`expr` fragment immediately followed
+/// by an arbitrary token).
+///
+/// # Examples
+///
+/// ```
+/// use kernel::register;
+///
+/// struct FakeBar(core::cell::Cell<u32>);
+///
+/// impl FakeBar {
+///     fn readl(&self, _offset: u32) -> u32 {
+///         self.0.get()
+///     }
+///
+///     fn writel(&self, value: u32, _offset: u32) {
+///         self.0.set(value);
+///     }
+/// }
+///
+/// register!(Boot0 @ (FakeBar) @ (0x0), ro {
+///     3:0     minor_rev as u8,
+///     7:4     major_rev as u8,
+///     23:20   impl_ as u32,
+///     28:24   arch as u32,
+///     chipset as u32 = composite(impl_, arch),
+/// });
+///
+/// let bar = FakeBar(core::cell::Cell::new(0x15200034));
+/// let boot0 = Boot0::read(&bar);
+/// assert_eq!(boot0.minor_rev(), 0x4);
+/// assert_eq!(boot0.major_rev(), 0x3);
+/// assert_eq!(boot0.impl_(), 0x2);
+/// assert_eq!(boot0.arch(), 0x15);
+/// assert_eq!(boot0.chipset(), 0x152);
+///
+/// register!(Scratch @ (FakeBar) @ (0x4) {
+///     31:0    value/set_value as u32,
+/// });
+///
+/// let mut scratch = Scratch::read(&bar);
+/// scratch.set_value(0xffff_ffff);
+/// assert_eq!(scratch.value(), 0xffff_ffff);
+/// ```
+#[macro_export]
+macro_rules! register {
+    (
+        $name:ident @ ($io:ty) @ ($offset:expr), ro {
+            $($fields:tt)*
+        }
+    ) => {
+        $crate::register!(@decl $name);
+        $crate::register!(@read $name @ ($io) @ ($offset));
+        $crate::register!(@fields $name { $($fields)* });
+    };
+    (
+        $name:ident @ ($io:ty) @ ($offset:expr) {
+            $($fields:tt)*
+        }
+    ) => {
+        $crate::register!(@decl $name);
+        $crate::register!(@read $name @ ($io) @ ($offset));
+        $crate::register!(@write $name @ ($io) @ ($offset));
+        $crate::register!(@fields $name { $($fields)* });
+    };
+
+    (@decl $name:ident) => {
+        #[derive(Copy, Clone, PartialEq, Eq)]
+        pub(crate) struct $name(u32);
+    };
+
+    (@read $name:ident @ ($io:ty) @ ($offset:expr)) => {
+        impl $name {
+            #[inline]
+            pub(crate) fn read(bar: &$io) -> Self {
+                Self(bar.readl($offset))
+            }
+        }
+    };
+
+    (@write $name:ident @ ($io:ty) @ ($offset:expr)) => {
+        impl $name {
+            #[inline]
+            pub(crate) fn write(bar: &$io, value: u32) {
+                bar.writel(value, $offset)
+            }
+        }
+    };
+
+    (
+        @fields $name:ident {
+            $(
+                $( $hi:literal : $lo:literal )? $field:ident $(/ $setter:ident)? as $ty:ty
+                    $( = composite($($part:ident),+ $(,)?) )?
+            ),+ $(,)?
+        }
+    ) => {
+        impl $name {
+            // Looks up the inclusive `(high, low)` bit range of one of this register's plain
+            // (non-composite) fields by name, for `composite(...)` fields to combine.
+            #[allow(dead_code)]
+            fn __field_range(field: &str) -> (u8, u8) {
+                $(
+                    $(
+                        if field == stringify!($field) {
+                            return ($hi, $lo);
+                        }
+                    )?
+                )+
+                unreachable!("register!: composite() referenced an unknown or composite field")
+            }
+
+            $(
+                $crate::register!(@field $field $(/ $setter)? as $ty
+                    $( , range($hi, $lo) )?
+                    $( , composite($($part),+) )?
+                );
+            )+
+        }
+    };
+
+    (@field $field:ident as $ty:ty, range($hi:literal, $lo:literal)) => {
+        #[inline]
+        pub(crate) fn $field(&self) -> $ty {
+            // Widened to `u128` while computing the mask: a full-width 32-bit field has
+            // `$hi - $lo + 1 == 32`, and `1u32 << 32` would overflow in a `u32` shift.
+            const MASK: u32 = (((1u128 << ($hi - $lo + 1)) - 1) << $lo) as u32;
+            ((self.0 & MASK) >> $lo) as $ty
+        }
+    };
+
+    (@field $field:ident / $setter:ident as $ty:ty, range($hi:literal, $lo:literal)) => {
+        $crate::register!(@field $field as $ty, range($hi, $lo));
+
+        #[inline]
+        pub(crate) fn $setter(&mut self, value: $ty) -> &mut Self {
+            // See the getter above for why this is computed via `u128`.
+            const MASK: u32 = (((1u128 << ($hi - $lo + 1)) - 1) << $lo) as u32;
+            self.0 = (self.0 & !MASK) | (((value as u32) << $lo) & MASK);
+            self
+        }
+    };
+
+    (@field $field:ident as $ty:ty, composite($($part:ident),+)) => {
+        #[inline]
+        pub(crate) fn $field(&self) -> $ty {
+            let mut mask: u32 = 0;
+            let mut lo: u8 = u8::MAX;
+
+            $(
+                let (part_hi, part_lo) = Self::__field_range(stringify!($part));
+                mask |= (((1u128 << (part_hi - part_lo + 1)) - 1) << part_lo) as u32;
+                if part_lo < lo {
+                    lo = part_lo;
+                }
+            )+
+
+            ((self.0 & mask) >> lo) as $ty
+        }
+    };
+}